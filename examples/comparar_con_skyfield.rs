@@ -2,15 +2,17 @@ use chrono::Utc;
 use predict_rs::predict::PredictObserver;
 use sgp4::{Constants, Elements};
 use std::fs;
+use tracking::observacion::CsvSink;
 use tracking::tle_loader;
 use tracking::validaciones::generar_comparacion;
 
-fn main() {
+#[tokio::main]
+async fn main() {
     println!("=== VALIDACIÓN DOPPLER ISS ===\n");
 
     println!("[1] Descargando TLE...");
 
-    let tle_data = match tle_loader::obtener_tle_por_nombre("ISS") {
+    let tle_data = match tle_loader::obtener_tle_por_nombre("ISS").await {
         Ok(data) => {
             println!("✓ {}", data.name);
 
@@ -61,7 +63,15 @@ fn main() {
     println!("\n[2] Generando datos...");
 
     let inicio = Utc::now();
-    match generar_comparacion(&observer, &elements, &constants, inicio, 90) {
+    let file = match fs::File::create("validacion_doppler/iss/doppler_output.csv") {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("✗ Error: {}", e);
+            return;
+        }
+    };
+    let mut sink = CsvSink::new(file);
+    match generar_comparacion(&observer, &elements, &constants, inicio, 90, &mut sink).await {
         Ok(_) => {
             println!("\n✓ CSV: validacion_doppler/iss/doppler_output.csv");
             println!("\nComparar con: python3 src/validaciones/validar_iss.py");
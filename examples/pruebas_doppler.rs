@@ -4,11 +4,12 @@ use sgp4::{Constants, Elements};
 use tracking::doppler_downlink;
 use tracking::{frequencies, tle_loader};
 
-fn main() {
+#[tokio::main]
+async fn main() {
     println!("=== VALIDACIÓN DOPPLER ===\n");
 
     println!("Obteniendo TLE de ISS...");
-    let tle_data = match tle_loader::obtener_tle_por_nombre("ISS") {
+    let tle_data = match tle_loader::obtener_tle_por_nombre("ISS").await {
         Ok(data) => {
             println!("✓ {}", data.name);
             data
@@ -77,8 +78,12 @@ fn main() {
     println!("{}", "-".repeat(50));
 
     // Obtener frecuencias reales de satélites
-    let iss_freq = frequencies::obtener_frecuencia_por_nombre("ISS").unwrap_or(145_800_000.0);
-    let ao91_freq = frequencies::obtener_frecuencia_por_nombre("AO-91").unwrap_or(145_960_000.0);
+    let iss_freq = frequencies::obtener_frecuencia_por_nombre("ISS")
+        .await
+        .unwrap_or(145_800_000.0);
+    let ao91_freq = frequencies::obtener_frecuencia_por_nombre("AO-91")
+        .await
+        .unwrap_or(145_960_000.0);
 
     let freq_bands = vec![
         ("ISS VHF", iss_freq),
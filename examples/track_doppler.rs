@@ -16,55 +16,201 @@ use chrono::{DateTime, Duration, Utc};
 use predict_rs::{observer::predict_observe_orbit, orbit::predict_orbit, predict::PredictObserver};
 use sgp4::{Constants, Elements};
 use tracking::doppler_downlink;
+use tracking::ephemeris::EphemerisCache;
+use tracking::observacion::{JsonLinesSink, Observacion, ObservationSink};
+use tracking::rig::{RigController, SimulatedRig};
 use tracking::tle_loader;
 
-/// Encuentra el próximo pase del satélite con elevación > elevación mínima
+/// Pase refinado con tiempos a resolución sub-segundo.
+#[derive(Debug, Clone, Copy)]
+struct Pase {
+    /// Adquisición de señal (cruce ascendente de la elevación mínima).
+    aos: DateTime<Utc>,
+    /// Pérdida de señal (cruce descendente de la elevación mínima).
+    los: DateTime<Utc>,
+    /// Time of Closest Approach / culminación (máxima elevación).
+    tca: DateTime<Utc>,
+    /// Elevación máxima del pase, en grados.
+    max_elevacion: f64,
+}
+
+/// Elevación del satélite (grados) en un instante dado.
+fn elevacion_en(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    t: DateTime<Utc>,
+) -> Option<f64> {
+    let sat_orbit = predict_orbit(elements, constants, t.timestamp() as f64).ok()?;
+    Some(predict_observe_orbit(observer, &sat_orbit).elevation.to_degrees())
+}
+
+/// Algoritmo de Neville sobre `(xs[i], ys[i])`, evaluado en `x`.
+///
+/// Devuelve el valor interpolado `P[0][n-1]` y su derivada `dP[0][n-1]`,
+/// propagando en paralelo la tabla triangular de la derivada.
+fn neville(xs: &[f64], ys: &[f64], x: f64) -> (f64, f64) {
+    let n = xs.len();
+    let mut p = ys.to_vec();
+    let mut dp = vec![0.0_f64; n];
+
+    for j in 1..n {
+        for i in 0..(n - j) {
+            let xi = xs[i];
+            let xj = xs[i + j];
+            let denom = xi - xj;
+            dp[i] = (p[i] - p[i + 1] + (x - xj) * dp[i] + (xi - x) * dp[i + 1]) / denom;
+            p[i] = ((x - xj) * p[i] + (xi - x) * p[i + 1]) / denom;
+        }
+    }
+
+    (p[0], dp[0])
+}
+
+/// Muestrea `2·(n/2) + 1` puntos `(segundos_relativos, elevación)` centrados en
+/// `center` (con `n` par, uno más que `n`).
+fn muestrear_elevacion(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    center: DateTime<Utc>,
+    paso_secs: f64,
+    n: usize,
+) -> Option<(Vec<f64>, Vec<f64>)> {
+    let mut xs = Vec::with_capacity(n);
+    let mut ys = Vec::with_capacity(n);
+    let half = (n as i64) / 2;
+    for k in -half..=half {
+        let dt = k as f64 * paso_secs;
+        let t = center + Duration::milliseconds((dt * 1000.0) as i64);
+        xs.push(dt);
+        ys.push(elevacion_en(observer, elements, constants, t)?);
+    }
+    Some((xs, ys))
+}
+
+/// Refina, por interpolación de Neville y pasos de Newton, el instante en que la
+/// elevación cruza `target` dentro del intervalo coarse `[t0, t1]`.
+fn refinar_cruce(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    t0: DateTime<Utc>,
+    t1: DateTime<Utc>,
+    target: f64,
+) -> Option<DateTime<Utc>> {
+    let center = t0 + (t1 - t0) / 2;
+    let paso = (t1 - t0).num_seconds().max(1) as f64 / 6.0;
+    let (xs, ys) = muestrear_elevacion(observer, elements, constants, center, paso, 6)?;
+
+    // Arrancar en el punto medio y aplicar Newton sobre elevación(t) - target = 0.
+    let mut x = 0.0;
+    for _ in 0..10 {
+        let (el, d_el) = neville(&xs, &ys, x);
+        if d_el.abs() < 1e-9 {
+            break;
+        }
+        let step = (el - target) / d_el;
+        x -= step;
+        if step.abs() < 1e-3 {
+            break;
+        }
+    }
+
+    Some(center + Duration::milliseconds((x * 1000.0) as i64))
+}
+
+/// Refina el instante de culminación (máxima elevación) resolviendo
+/// `dElevación/dt = 0` con pasos de Newton sobre la derivada interpolada.
+fn refinar_culminacion(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    aprox: DateTime<Utc>,
+    paso: f64,
+) -> Option<(DateTime<Utc>, f64)> {
+    let (xs, ys) = muestrear_elevacion(observer, elements, constants, aprox, paso, 6)?;
+
+    let mut x = 0.0;
+    for _ in 0..10 {
+        // Derivada primera de la tabla de Neville; segunda por diferencia finita
+        // de la primera.
+        let h = 1e-2;
+        let (_, d1) = neville(&xs, &ys, x);
+        let (_, d1b) = neville(&xs, &ys, x + h);
+        let d2 = (d1b - d1) / h;
+        if d2.abs() < 1e-9 {
+            break;
+        }
+        let step = d1 / d2;
+        x -= step;
+        if step.abs() < 1e-3 {
+            break;
+        }
+    }
+
+    let (el, _) = neville(&xs, &ys, x);
+    Some((aprox + Duration::milliseconds((x * 1000.0) as i64), el))
+}
+
+/// Encuentra el próximo pase del satélite con elevación > elevación mínima,
+/// refinando AOS, LOS y culminación a resolución sub-segundo.
 fn encontrar_proximo_pase(
     observer: &PredictObserver,
     elements: &Elements,
     constants: &Constants,
     start_time: DateTime<Utc>,
     max_hours: i64,
-) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+) -> Option<Pase> {
     let mut current_time = start_time;
     let end_search = start_time + Duration::hours(max_hours);
     let mut in_pass = false;
-    let mut aos_time = None;
+    let mut aos_coarse: Option<DateTime<Utc>> = None;
+    let mut prev_time = current_time;
+    let min_el = observer.min_elevation.to_degrees();
+
+    // Barrido coarse servido desde la caché de efemérides: propaga SGP4 sobre una
+    // grilla de 1 min e interpola los instantes intermedios, en vez de propagar en
+    // cada tick.
+    let mut cache = EphemerisCache::new(observer, elements, constants, 60.0, 120.0, 4);
 
     // Buscar en intervalos de 1 minuto
     while current_time < end_search {
-        let sat_orbit = predict_orbit(elements, constants, current_time.timestamp() as f64).ok()?;
-        let observation = predict_observe_orbit(observer, &sat_orbit);
-
-        let elevation_deg = observation.elevation.to_degrees();
-        let is_visible = elevation_deg > observer.min_elevation.to_degrees();
+        let elevation_deg = cache.observe(current_time)?.elevation;
+        let is_visible = elevation_deg > min_el;
 
         if is_visible && !in_pass {
-            // AOS - Acquisition of Signal
-            aos_time = Some(current_time);
+            // AOS coarse: el cruce está en [prev_time, current_time].
+            aos_coarse = Some(prev_time);
             in_pass = true;
         } else if !is_visible && in_pass {
-            // LOS - Loss of Signal
-            return Some((aos_time.unwrap(), current_time));
+            // LOS coarse: el cruce está en [prev_time, current_time].
+            let aos_c = aos_coarse.unwrap();
+            let aos = refinar_cruce(observer, elements, constants, aos_c, aos_c + Duration::minutes(1), min_el)?;
+            let los = refinar_cruce(observer, elements, constants, prev_time, current_time, min_el)?;
+
+            // Culminación: punto medio del pase refinado como semilla.
+            let seed = aos + (los - aos) / 2;
+            let (tca, max_elevacion) =
+                refinar_culminacion(observer, elements, constants, seed, 30.0)?;
+
+            return Some(Pase {
+                aos,
+                los,
+                tca,
+                max_elevacion,
+            });
         }
 
+        prev_time = current_time;
         current_time += Duration::minutes(1);
     }
 
     None
 }
 
-/// Trackea un pase completo del satélite con corrección de Doppler
-#[derive(Debug)]
-#[allow(dead_code)]
-struct Observacion {
-    tiempo: DateTime<Utc>,
-    elevacion: f64,
-    azimut: f64,
-    doppler_hz: f64,
-    range_rate: f64,
-}
-
+/// Trackea un pase completo del satélite con corrección de Doppler,
+/// transmitiendo cada muestra al sumidero de observaciones configurado.
 fn trackear_pase(
     observer: &PredictObserver,
     elements: &Elements,
@@ -72,6 +218,9 @@ fn trackear_pase(
     freq_tx: f64,
     aos: DateTime<Utc>,
     los: DateTime<Utc>,
+    cadencia: Duration,
+    rig: &mut dyn RigController,
+    sink: &mut dyn ObservationSink,
 ) {
     println!("\n=== TRACKING ===");
     println!("AOS: {} UTC", aos.format("%H:%M:%S"));
@@ -89,19 +238,22 @@ fn trackear_pase(
     println!("{}", "-".repeat(60));
 
     let mut current_time = aos;
-    let update_interval = 5; // actualizar cada 5 segundos
 
-    let mut observaciones: Vec<Observacion> = Vec::new();
+    // Durante el pase la sintonía se actualiza cada pocos segundos: servir las
+    // observaciones desde la caché (grilla gruesa de 30 s interpolada) evita una
+    // propagación SGP4 por tick.
+    let mut cache = EphemerisCache::new(observer, elements, constants, 30.0, 90.0, 4);
 
     while current_time <= los {
-        // Obtener posición del satélite
-        let sat_orbit = predict_orbit(elements, constants, current_time.timestamp() as f64)
+        // Obtener posición del satélite desde la caché de efemérides.
+        let observation = cache
+            .observe(current_time)
             .expect("Error al predecir órbita");
-        let observation = predict_observe_orbit(observer, &sat_orbit);
 
-        let elevation_deg = observation.elevation.to_degrees();
-        let azimuth_deg = observation.azimuth.to_degrees();
-        let range_rate = observation.range_rate * 1000.0; // Convertir a m/s
+        let elevation_deg = observation.elevation;
+        let azimuth_deg = observation.azimuth;
+        let range_rate = observation.range_rate; // m/s
+        let range_m = observation.range; // m
 
         // Verificar que seguimos visible
         if elevation_deg < observer.min_elevation.to_degrees() {
@@ -111,7 +263,7 @@ fn trackear_pase(
                 elevation_deg,
                 azimuth_deg
             );
-            current_time += Duration::seconds(update_interval);
+            current_time += cadencia;
             continue;
         }
 
@@ -119,6 +271,14 @@ fn trackear_pase(
         let freq_rx = doppler_downlink(freq_tx, range_rate);
         let doppler_hz = freq_rx - freq_tx;
 
+        // Enviar sintonía y apuntamiento al hardware (o al backend simulado).
+        if let Err(e) = rig.set_freq(freq_rx) {
+            eprintln!("⚠ Error al sintonizar el radio: {}", e);
+        }
+        if let Err(e) = rig.set_position(azimuth_deg, elevation_deg) {
+            eprintln!("⚠ Error al apuntar el rotor: {}", e);
+        }
+
         println!(
             "{:<10} | {:>7.2} {:>7.2} | {:>11.2} {:>12.6}",
             current_time.format("%H:%M:%S"),
@@ -128,30 +288,39 @@ fn trackear_pase(
             freq_rx / 1_000_000.0
         );
 
-        // Crear y almacenar la observación
+        // Transmitir la observación al sumidero configurado.
         let observacion = Observacion {
             tiempo: current_time,
-            elevacion: elevation_deg,
-            azimut: azimuth_deg,
-            doppler_hz,
+            sat: elements.object_name.clone().unwrap_or_default(),
+            norad_id: elements.norad_id,
+            band_hz: freq_tx,
+            range: range_m,
             range_rate,
+            elevation: elevation_deg,
+            azimuth: azimuth_deg,
+            doppler_hz,
+            freq_rx,
         };
-        observaciones.push(observacion);
+        if let Err(e) = sink.write(&observacion) {
+            eprintln!("⚠ Error al publicar observación: {}", e);
+        }
 
-        current_time += Duration::seconds(update_interval);
+        current_time += cadencia;
     }
 
-    println!("\n✓ Pase completado\n");
+    if let Err(e) = sink.flush() {
+        eprintln!("⚠ Error al vaciar el sumidero: {}", e);
+    }
 
-    // Mostrar todas las observaciones al final
-    println!("Observaciones completas: {:?}", observaciones);
+    println!("\n✓ Pase completado\n");
 }
 
-fn main() {
+#[tokio::main]
+async fn main() {
     println!("=== TRACKING CON CORRECCIÓN DOPPLER ===\n");
 
     // Obtener TLE actualizado de la ISS
-    let tle_data = match tle_loader::obtener_tle_por_nombre("ISS") {
+    let tle_data = match tle_loader::obtener_tle_por_nombre("ISS").await {
         Ok(data) => {
             println!("✓ TLE: {}", data.name);
             data
@@ -211,7 +380,8 @@ fn main() {
     let now = Utc::now();
 
     match encontrar_proximo_pase(&observer, &elements, &constants, now, 24) {
-        Some((aos, los)) => {
+        Some(pase) => {
+            let Pase { aos, los, tca, max_elevacion } = pase;
             let time_until_aos = aos - now;
             println!("✓ Pase encontrado");
             println!(
@@ -219,6 +389,11 @@ fn main() {
                 aos.format("%Y-%m-%d %H:%M:%S UTC"),
                 time_until_aos.num_seconds() as f64 / 60.0
             );
+            println!(
+                "  Culminación (TCA): {} a {:.2}° de elevación",
+                tca.format("%Y-%m-%d %H:%M:%S UTC"),
+                max_elevacion
+            );
 
             if time_until_aos.num_seconds() > 0 {
                 println!("\n⏳ Esperando hasta AOS...");
@@ -226,8 +401,15 @@ fn main() {
                 println!("   (Para este ejemplo, simularemos el tracking del pase)\n");
             }
 
-            // Trackear el pase completo
-            trackear_pase(&observer, &elements, &constants, freq_tx, aos, los);
+            // Trackear el pase completo (backend simulado / dry-run),
+            // emitiendo las observaciones en JSON-lines por stdout.
+            let mut rig = SimulatedRig;
+            let mut sink = JsonLinesSink::new(std::io::stdout());
+            // Cadencia de actualización de sintonía/apuntamiento durante el pase.
+            let cadencia = Duration::seconds(5);
+            trackear_pase(
+                &observer, &elements, &constants, freq_tx, aos, los, cadencia, &mut rig, &mut sink,
+            );
 
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
             println!("IMPORTANTE - Cómo usar esta información:");
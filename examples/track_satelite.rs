@@ -4,12 +4,13 @@ use sgp4::{Constants, Elements};
 use std::fs;
 use tracking::{frequencies, tle_loader};
 
-fn main() {
+#[tokio::main]
+async fn main() {
     println!("=== VALIDACIÓN DOPPLER - SATÉLITE ===\n");
 
     println!("[1] Descargando TLE...");
 
-    let tle_data = match tle_loader::obtener_tle_por_nombre("AO-91") {
+    let tle_data = match tle_loader::obtener_tle_por_nombre("AO-91").await {
         Ok(data) => {
             println!("✓ {}", data.name);
 
@@ -48,8 +49,9 @@ fn main() {
 
     // Obtener frecuencia
     println!("\n[2] Obteniendo frecuencia...");
-    let freq_hz =
-        frequencies::obtener_frecuencia_por_nombre("AO-91").expect("Frecuencia no encontrada");
+    let freq_hz = frequencies::obtener_frecuencia_por_nombre("AO-91")
+        .await
+        .expect("Frecuencia no encontrada");
     println!("✓ {:.3} MHz", freq_hz / 1_000_000.0);
 
     // Generar datos
@@ -1,4 +1,6 @@
+use crate::timescale;
 use chrono::{DateTime, Utc};
+use hifitime::Epoch;
 use predict_rs::predict::PredictObserver;
 use sgp4::{Constants, Elements, MinutesSinceEpoch};
 use std::f64::consts::PI;
@@ -69,33 +71,187 @@ fn teme_to_ecef(teme_pos: [f64; 3], gmst_rad: f64) -> [f64; 3] {
     ]
 }
 
-pub fn calcular_rango(
+/// Observación topocéntrica derivada íntegramente de vectores ECEF WGS84.
+#[derive(Debug, Clone, Copy)]
+pub struct LookAngles {
+    /// Offsets locales Este/Norte/Arriba respecto del observador, en metros.
+    pub enu: [f64; 3],
+    /// Azimut en grados, normalizado a 0–360°.
+    pub azimuth: f64,
+    /// Elevación en grados.
+    pub elevation: f64,
+    /// Slant range en metros.
+    pub range: f64,
+}
+
+/// Calcula el conjunto completo de ángulos topocéntricos (ENU + az/el + rango)
+/// a partir de las posiciones ECEF del observador y del satélite.
+///
+/// Proyecta el vector observador→satélite sobre la base local Este/Norte/Arriba
+/// definida en la latitud/longitud geodésicas del observador, evitando así
+/// depender de `predict_rs` para obtener una observación consistente con WGS84.
+pub fn look_angles(
     observer: &PredictObserver,
     elements: &Elements,
     constants: &Constants,
     when: DateTime<Utc>,
+) -> Option<LookAngles> {
+    let (obs_ecef, sat_ecef) = ecef_observer_satelite(observer, elements, constants, when)?;
+    // `ecef_observer_satelite` trabaja en km; `topocentric` espera metros.
+    topocentric(observer, escalar(obs_ecef, 1000.0), escalar(sat_ecef, 1000.0))
+}
+
+/// Proyecta el vector observador→satélite (ambos en ECEF, metros) sobre la base
+/// local ENU del observador y devuelve los ángulos topocéntricos.
+fn topocentric(
+    observer: &PredictObserver,
+    obs_ecef_m: [f64; 3],
+    sat_ecef_m: [f64; 3],
+) -> Option<LookAngles> {
+    let d = [
+        sat_ecef_m[0] - obs_ecef_m[0],
+        sat_ecef_m[1] - obs_ecef_m[1],
+        sat_ecef_m[2] - obs_ecef_m[2],
+    ];
+
+    let lat = observer.latitude;
+    let lon = observer.longitude;
+
+    // Base local ENU en el observador.
+    let east = [-lon.sin(), lon.cos(), 0.0];
+    let north = [-lat.sin() * lon.cos(), -lat.sin() * lon.sin(), lat.cos()];
+    let up = [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()];
+
+    let e = dot(&d, &east);
+    let n = dot(&d, &north);
+    let u = dot(&d, &up);
+
+    let range = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+    if range == 0.0 {
+        return None;
+    }
+
+    let elevation = (u / range).asin().to_degrees();
+    let mut azimuth = e.atan2(n).to_degrees();
+    if azimuth < 0.0 {
+        azimuth += 360.0;
+    }
+
+    Some(LookAngles {
+        enu: [e, n, u],
+        azimuth,
+        elevation,
+        range,
+    })
+}
+
+/// Multiplica un vector de 3 componentes por un escalar.
+fn escalar(v: [f64; 3], k: f64) -> [f64; 3] {
+    [v[0] * k, v[1] * k, v[2] * k]
+}
+
+/// Observación topocéntrica usando posiciones precisas SP3 como fuente, en
+/// lugar de propagar con SGP4.
+///
+/// Devuelve los ángulos de observación junto con el range-rate (m/s), obtenido
+/// proyectando la velocidad ECEF interpolada sobre la línea de vista.
+pub fn observar_sp3(
+    observer: &PredictObserver,
+    ephem: &mut crate::sp3::Sp3Ephemeris,
+    when: DateTime<Utc>,
+) -> Option<(LookAngles, f64)> {
+    let sample = ephem.interpolate(when).ok()?;
+
+    let lat_deg = observer.latitude * 180.0 / PI;
+    let lon_deg = observer.longitude * 180.0 / PI;
+    let obs_ecef_m = escalar(geodetic_to_ecef(lat_deg, lon_deg, observer.altitude), 1000.0);
+
+    let la = topocentric(observer, obs_ecef_m, sample.position)?;
+
+    // Range-rate = componente radial de la velocidad del satélite (el observador
+    // es fijo en ECEF).
+    let d = [
+        sample.position[0] - obs_ecef_m[0],
+        sample.position[1] - obs_ecef_m[1],
+        sample.position[2] - obs_ecef_m[2],
+    ];
+    let range_rate = dot(&d, &sample.velocity) / la.range;
+
+    Some((la, range_rate))
+}
+
+/// Slant range (metros) calculado a partir de efemérides precisas SP3.
+pub fn calcular_rango_sp3(
+    observer: &PredictObserver,
+    ephem: &mut crate::sp3::Sp3Ephemeris,
+    when: DateTime<Utc>,
 ) -> Option<f64> {
-    // Calcular minutos desde epoch
-    let epoch_timestamp = elements.datetime.and_utc().timestamp() as f64;
-    let when_timestamp = when.timestamp() as f64;
-    let minutes_since_epoch = (when_timestamp - epoch_timestamp) / 60.0;
+    observar_sp3(observer, ephem, when).map(|(la, _)| la.range)
+}
+
+/// Producto escalar de dos vectores de 3 componentes.
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Devuelve las posiciones ECEF (km) del observador y del satélite en `when`.
+fn ecef_observer_satelite(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    when: DateTime<Utc>,
+) -> Option<([f64; 3], [f64; 3])> {
+    ecef_observer_satelite_epoch(observer, elements, constants, timescale::epoch_from_utc(when))
+}
+
+/// Variante de [`ecef_observer_satelite`] que opera directamente sobre una época
+/// con escala explícita.
+///
+/// La propagación SGP4 usa la diferencia en escala TAI (continua) respecto de la
+/// época del TLE, de modo que el resultado no salta al cruzar un segundo
+/// intercalar; GMST se evalúa sobre la escala UTC (UT1≈UTC).
+fn ecef_observer_satelite_epoch(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    when: Epoch,
+) -> Option<([f64; 3], [f64; 3])> {
+    let epoch_tle = timescale::epoch_from_utc(elements.datetime.and_utc());
+    let minutes_since_epoch = (timescale::tai_seconds(when) - timescale::tai_seconds(epoch_tle)) / 60.0;
 
-    // Propagar satélite con SGP4
     let prediction = constants
         .propagate(MinutesSinceEpoch(minutes_since_epoch))
         .ok()?;
 
-    // Calcular GMST para convertir TEME a ECEF
-    let gmst = calculate_gmst(when_timestamp);
-
-    // Convertir posición del satélite de TEME a ECEF
+    let gmst = calculate_gmst(timescale::utc_seconds(when));
     let sat_ecef = teme_to_ecef(prediction.position, gmst);
 
-    // Convertir observador a ECEF
     let lat_deg = observer.latitude * 180.0 / PI;
     let lon_deg = observer.longitude * 180.0 / PI;
     let obs_ecef = geodetic_to_ecef(lat_deg, lon_deg, observer.altitude);
 
+    Some((obs_ecef, sat_ecef))
+}
+
+pub fn calcular_rango(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    when: DateTime<Utc>,
+) -> Option<f64> {
+    calcular_rango_epoch(observer, elements, constants, timescale::epoch_from_utc(when))
+}
+
+/// Variante de [`calcular_rango`] que acepta una época con escala de tiempo
+/// explícita (UTC/TAI/GPST).
+pub fn calcular_rango_epoch(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    when: Epoch,
+) -> Option<f64> {
+    let (obs_ecef, sat_ecef) = ecef_observer_satelite_epoch(observer, elements, constants, when)?;
+
     // Vector desde observador a satélite
     let dx = sat_ecef[0] - obs_ecef[0];
     let dy = sat_ecef[1] - obs_ecef[1];
@@ -113,12 +269,33 @@ pub fn calcular_doppler(
     freq_tx: f64,
     when: DateTime<Utc>,
     dt_secs: i64,
+) -> Option<f64> {
+    calcular_doppler_epoch(
+        observer,
+        elements,
+        constants,
+        freq_tx,
+        timescale::epoch_from_utc(when),
+        dt_secs,
+    )
+}
+
+/// Variante de [`calcular_doppler`] que acepta una época con escala de tiempo
+/// explícita. El incremento `dt_secs` se aplica en escala TAI, evitando que un
+/// segundo intercalar contamine el range-rate.
+pub fn calcular_doppler_epoch(
+    observer: &PredictObserver,
+    elements: &Elements,
+    constants: &Constants,
+    freq_tx: f64,
+    when: Epoch,
+    dt_secs: i64,
 ) -> Option<f64> {
     // Calcular rango en dos momentos para obtener range_rate por diferencias finitas
-    let rango1 = calcular_rango(observer, elements, constants, when)?;
+    let rango1 = calcular_rango_epoch(observer, elements, constants, when)?;
 
-    let when2 = when + chrono::Duration::seconds(dt_secs);
-    let rango2 = calcular_rango(observer, elements, constants, when2)?;
+    let when2 = when + hifitime::Duration::from_seconds(dt_secs as f64);
+    let rango2 = calcular_rango_epoch(observer, elements, constants, when2)?;
 
     let range_rate = (rango2 - rango1) / (dt_secs as f64); // m/s
 
@@ -0,0 +1,211 @@
+//! Caché de efemérides interpolada para evitar llamadas a SGP4 por tick
+//!
+//! Sin caché, el buscador de pases y el bucle de tracking llamarían a
+//! `predict_orbit` en cada tick (cada 1 min o 5 s), lo cual es caro al seguir
+//! muchos satélites o al densificar el muestreo. Esta caché propaga SGP4 en una grilla gruesa
+//! (p. ej. cada 30–60 s) para obtener rango/azimut/elevación en el marco del
+//! observador y luego sirve instantes intermedios por interpolación polinómica
+//! (Neville) de esas muestras.
+
+use chrono::{DateTime, Utc};
+use predict_rs::{observer::predict_observe_orbit, orbit::predict_orbit, predict::PredictObserver};
+use sgp4::{Constants, Elements};
+use std::collections::VecDeque;
+
+/// Muestra gruesa en el marco del observador.
+#[derive(Debug, Clone, Copy)]
+struct CachedSample {
+    /// Época en segundos Unix.
+    t: f64,
+    range: f64,
+    range_rate: f64,
+    /// Azimut en grados, desenrollado (puede exceder 0–360° para ser continuo).
+    az: f64,
+    el: f64,
+}
+
+/// Observación servida por la caché (rango en m, range rate en m/s, ángulos en grados).
+#[derive(Debug, Clone, Copy)]
+pub struct CachedObservation {
+    pub range: f64,
+    pub range_rate: f64,
+    pub azimuth: f64,
+    pub elevation: f64,
+}
+
+/// Caché de efemérides con un pequeño ring buffer de muestras gruesas.
+pub struct EphemerisCache<'a> {
+    observer: &'a PredictObserver,
+    elements: &'a Elements,
+    constants: &'a Constants,
+    /// Paso de la grilla gruesa, en segundos.
+    step_secs: f64,
+    /// Ventana máxima aceptable (segundos) de las muestras respecto de la consulta.
+    max_window_secs: f64,
+    /// Cantidad máxima de muestras a usar por interpolación.
+    k: usize,
+    buffer: VecDeque<CachedSample>,
+}
+
+impl<'a> EphemerisCache<'a> {
+    /// Crea una caché sobre una grilla de `step_secs` que interpola usando hasta
+    /// `k` muestras dentro de `max_window_secs` de la consulta.
+    pub fn new(
+        observer: &'a PredictObserver,
+        elements: &'a Elements,
+        constants: &'a Constants,
+        step_secs: f64,
+        max_window_secs: f64,
+        k: usize,
+    ) -> Self {
+        Self {
+            observer,
+            elements,
+            constants,
+            step_secs,
+            max_window_secs,
+            k: k.max(2),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Observación en `at`, interpolada desde la grilla gruesa cuando es posible
+    /// o propagada directamente con SGP4 si la ventana no alcanza a encuadrar `t`.
+    pub fn observe(&mut self, at: DateTime<Utc>) -> Option<CachedObservation> {
+        let t = at.timestamp() as f64 + f64::from(at.timestamp_subsec_nanos()) * 1e-9;
+
+        self.fill_forward(t);
+
+        // Seleccionar las muestras cercanas dentro de la ventana permitida.
+        let mut near: Vec<CachedSample> = self
+            .buffer
+            .iter()
+            .copied()
+            .filter(|s| (s.t - t).abs() <= self.max_window_secs)
+            .collect();
+
+        let brackets = near.iter().any(|s| s.t <= t) && near.iter().any(|s| s.t >= t);
+        if !brackets || near.len() < 2 {
+            // La ventana no encuadra a t: propagar directamente.
+            return self.sample_at(t).map(Into::into);
+        }
+
+        near.sort_by(|a, b| {
+            (a.t - t)
+                .abs()
+                .partial_cmp(&(b.t - t).abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        near.truncate(self.k);
+        near.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap_or(std::cmp::Ordering::Equal));
+
+        let xs: Vec<f64> = near.iter().map(|s| s.t).collect();
+        Some(CachedObservation {
+            range: neville(&xs, &near.iter().map(|s| s.range).collect::<Vec<_>>(), t),
+            range_rate: neville(&xs, &near.iter().map(|s| s.range_rate).collect::<Vec<_>>(), t),
+            azimuth: normalizar_az(neville(&xs, &near.iter().map(|s| s.az).collect::<Vec<_>>(), t)),
+            elevation: neville(&xs, &near.iter().map(|s| s.el).collect::<Vec<_>>(), t),
+        })
+    }
+
+    /// Garantiza que el buffer cubra hacia adelante hasta `t`, agregando muestras
+    /// de grilla y descartando las viejas para acotar el tamaño del ring buffer.
+    fn fill_forward(&mut self, t: f64) {
+        let capacity = self.k + 4;
+
+        // Punto de grilla inicial alineado por debajo de t.
+        if self.buffer.is_empty() {
+            let start = (t / self.step_secs).floor() * self.step_secs;
+            // Sembrar algunas muestras anteriores para poder encuadrar t.
+            for i in -2..0 {
+                if let Some(s) = self.sample_at(start + i as f64 * self.step_secs) {
+                    self.buffer.push_back(s);
+                }
+            }
+            if let Some(s) = self.sample_at(start) {
+                self.buffer.push_back(s);
+            }
+        }
+
+        // Extender hacia adelante mientras la última muestra no supere a t + margen.
+        while self
+            .buffer
+            .back()
+            .map(|s| s.t < t + self.max_window_secs)
+            .unwrap_or(false)
+        {
+            let next_t = self.buffer.back().unwrap().t + self.step_secs;
+            match self.sample_at(next_t) {
+                Some(s) => self.buffer.push_back(s),
+                None => break,
+            }
+            while self.buffer.len() > capacity {
+                self.buffer.pop_front();
+            }
+        }
+    }
+
+    /// Propaga SGP4 y proyecta al marco del observador en la época `t_unix`,
+    /// desenrollando el azimut respecto de la última muestra del buffer.
+    fn sample_at(&self, t_unix: f64) -> Option<CachedSample> {
+        let orbit = predict_orbit(self.elements, self.constants, t_unix).ok()?;
+        let obs = predict_observe_orbit(self.observer, &orbit);
+
+        let az_deg = obs.azimuth.to_degrees();
+        let az = match self.buffer.back() {
+            Some(prev) => desenrollar_az(prev.az, az_deg),
+            None => az_deg,
+        };
+
+        Some(CachedSample {
+            t: t_unix,
+            range: obs.range * 1000.0,
+            range_rate: obs.range_rate * 1000.0,
+            az,
+            el: obs.elevation.to_degrees(),
+        })
+    }
+}
+
+impl From<CachedSample> for CachedObservation {
+    fn from(s: CachedSample) -> Self {
+        CachedObservation {
+            range: s.range,
+            range_rate: s.range_rate,
+            azimuth: normalizar_az(s.az),
+            elevation: s.el,
+        }
+    }
+}
+
+/// Desenrolla `az` para que sea continuo respecto de `prev` (evita el salto
+/// 0/360° al interpolar).
+fn desenrollar_az(prev: f64, az: f64) -> f64 {
+    let mut a = az;
+    while a - prev > 180.0 {
+        a -= 360.0;
+    }
+    while a - prev < -180.0 {
+        a += 360.0;
+    }
+    a
+}
+
+/// Reduce un azimut desenrollado al rango 0–360°.
+fn normalizar_az(az: f64) -> f64 {
+    az.rem_euclid(360.0)
+}
+
+/// Algoritmo de Neville sobre `(xs[i], ys[i])`, evaluado en `x`.
+fn neville(xs: &[f64], ys: &[f64], x: f64) -> f64 {
+    let n = xs.len();
+    let mut p = ys.to_vec();
+    for j in 1..n {
+        for i in 0..(n - j) {
+            let xi = xs[i];
+            let xj = xs[i + j];
+            p[i] = ((x - xj) * p[i] + (xi - x) * p[i + 1]) / (xi - xj);
+        }
+    }
+    p[0]
+}
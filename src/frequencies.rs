@@ -1,10 +1,52 @@
 //! Frecuencias de downlink de satélites LEO
 //!
 //! Este módulo descarga frecuencias actualizadas desde SatNOGS DB API
-//! (https://db.satnogs.org/api/transmitters/)
+//! (https://db.satnogs.org/api/transmitters/) mediante un cliente HTTP nativo,
+//! parseando la respuesta JSON con serde.
 
+use serde::Deserialize;
 use std::io;
-use std::process::Command;
+
+/// Transmisor activo tal como lo describe la API de SatNOGS.
+///
+/// Sólo se modelan los campos que usamos; serde ignora el resto.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Transmitter {
+    #[serde(default)]
+    pub description: String,
+    pub uplink_low: Option<f64>,
+    pub downlink_low: Option<f64>,
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl Transmitter {
+    /// Indica si el transmisor está activo.
+    fn is_active(&self) -> bool {
+        self.status.as_deref().map(|s| s == "active").unwrap_or(true)
+    }
+
+    /// Puntaje de preferencia: beacons y transmisores simples primero, luego el
+    /// resto de los downlinks válidos.
+    fn downlink_priority(&self) -> Option<i32> {
+        let downlink = self.downlink_low?;
+        if downlink <= 0.0 {
+            return None;
+        }
+        let desc = self.description.to_lowercase();
+        let is_beacon = desc.contains("beacon");
+        let is_transmitter = self.kind.as_deref() == Some("Transmitter");
+        Some(match (is_beacon, is_transmitter) {
+            (true, _) => 0,
+            (false, true) => 1,
+            (false, false) => 2,
+        })
+    }
+}
 
 /// Estructura con información completa de frecuencias de un satélite
 #[derive(Debug, Clone)]
@@ -19,107 +61,60 @@ pub struct SatelliteFrequencies {
     pub mode: String,
 }
 
-/// Descarga frecuencias desde SatNOGS DB API
+impl SatelliteFrequencies {
+    /// Selecciona el mejor downlink de una lista de transmisores, prefiriendo el
+    /// de mayor prioridad (beacon/transmisor) por sobre el primero disponible.
+    fn from_transmitters(transmitters: &[Transmitter], norad_id: u32) -> io::Result<Self> {
+        let best = transmitters
+            .iter()
+            .filter(|t| t.is_active())
+            .filter_map(|t| t.downlink_priority().map(|p| (p, t)))
+            .min_by_key(|(p, _)| *p)
+            .map(|(_, t)| t)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "No se encontró frecuencia de downlink en SatNOGS",
+                )
+            })?;
+
+        Ok(Self {
+            name: best.description.clone(),
+            norad_id,
+            downlink_hz: best.downlink_low.unwrap_or(0.0),
+            uplink_hz: best.uplink_low,
+            mode: best.mode.clone().unwrap_or_else(|| "Unknown".to_string()),
+        })
+    }
+}
+
+/// Descarga todos los transmisores activos de un satélite desde SatNOGS.
 ///
 /// # Argumentos
 /// * `norad_id` - El ID NORAD del satélite
-pub fn descargar_frecuencias_satnogs(norad_id: u32) -> io::Result<SatelliteFrequencies> {
+pub async fn descargar_frecuencias_satnogs(norad_id: u32) -> io::Result<Vec<Transmitter>> {
     let url = format!(
         "https://db.satnogs.org/api/transmitters/?satellite__norad_cat_id={}&format=json&status=active",
         norad_id
     );
 
-    let output = Command::new("curl").args(["-s", &url]).output()?;
-
-    if !output.status.success() {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "Error al descargar frecuencias desde SatNOGS",
-        ));
-    }
-
-    let content = String::from_utf8_lossy(&output.stdout);
-
-    parse_satnogs_json(&content, norad_id)
+    let transmitters = reqwest::get(&url)
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(to_io)?
+        .json::<Vec<Transmitter>>()
+        .await
+        .map_err(to_io)?;
+
+    Ok(transmitters
+        .into_iter()
+        .filter(Transmitter::is_active)
+        .collect())
 }
 
-/// Parser simple de JSON de SatNOGS
-fn parse_satnogs_json(json: &str, norad_id: u32) -> io::Result<SatelliteFrequencies> {
-    // Buscar el primer objeto con "downlink_low" válido
-    // JSON de SatNOGS viene en una sola línea, así que buscamos patrones
-
-    // Extraer descripción del primer transmisor
-    let description = if let Some(desc_start) = json.find("\"description\":\"") {
-        let desc_start = desc_start + 15; // Saltar "description":"
-        if let Some(desc_end) = json[desc_start..].find('\"') {
-            json[desc_start..desc_start + desc_end].to_string()
-        } else {
-            String::new()
-        }
-    } else {
-        String::new()
-    };
-
-    // Buscar downlink_low (primer valor no nulo)
-    let downlink_hz = if let Some(down_start) = json.find("\"downlink_low\":") {
-        let down_start = down_start + 15; // Saltar "downlink_low":
-        if let Some(comma_pos) = json[down_start..].find(',') {
-            let num_str = json[down_start..down_start + comma_pos].trim();
-            if num_str != "null" {
-                num_str.parse::<f64>().ok()
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-
-    // Buscar uplink_low
-    let uplink_hz = if let Some(up_start) = json.find("\"uplink_low\":") {
-        let up_start = up_start + 13; // Saltar "uplink_low":
-        if let Some(comma_pos) = json[up_start..].find(',') {
-            let num_str = json[up_start..up_start + comma_pos].trim();
-            if num_str != "null" {
-                num_str.parse::<f64>().ok()
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-
-    // Buscar mode
-    let mode = if let Some(mode_start) = json.find("\"mode\":\"") {
-        let mode_start = mode_start + 8; // Saltar "mode":"
-        if let Some(mode_end) = json[mode_start..].find('\"') {
-            json[mode_start..mode_start + mode_end].to_string()
-        } else {
-            String::from("Unknown")
-        }
-    } else {
-        String::from("Unknown")
-    };
-
-    let downlink = downlink_hz.ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            "No se encontró frecuencia de downlink en SatNOGS",
-        )
-    })?;
-
-    Ok(SatelliteFrequencies {
-        name: description,
-        norad_id,
-        downlink_hz: downlink,
-        uplink_hz,
-        mode,
-    })
+/// Convierte un error de `reqwest` en `io::Error`.
+fn to_io(err: reqwest::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, format!("Error HTTP SatNOGS: {}", err))
 }
 
 /// Base de datos local de satélites con sus frecuencias (fallback)
@@ -175,7 +170,7 @@ fn get_satellite_info_local(satellite_name: &str) -> Option<SatelliteFrequencies
 ///
 /// # Argumentos
 /// * `satellite_name` - Nombre del satélite o NORAD ID como string
-pub fn get_satellite_info(satellite_name: &str) -> Option<SatelliteFrequencies> {
+pub async fn get_satellite_info(satellite_name: &str) -> Option<SatelliteFrequencies> {
     // Primero intentar obtener NORAD ID
     let norad_id = if let Ok(id) = satellite_name.parse::<u32>() {
         // Ya es un NORAD ID
@@ -186,11 +181,18 @@ pub fn get_satellite_info(satellite_name: &str) -> Option<SatelliteFrequencies>
     };
 
     // Intentar descargar desde SatNOGS
-    match descargar_frecuencias_satnogs(norad_id) {
-        Ok(freq) => {
-            println!("✓ Frecuencias desde SatNOGS");
-            Some(freq)
-        }
+    match descargar_frecuencias_satnogs(norad_id).await {
+        Ok(transmitters) => match SatelliteFrequencies::from_transmitters(&transmitters, norad_id) {
+            Ok(freq) => {
+                println!("✓ Frecuencias desde SatNOGS");
+                Some(freq)
+            }
+            Err(e) => {
+                eprintln!("⚠ Error SatNOGS: {}", e);
+                println!("⚠ Usando base de datos local");
+                get_satellite_info_local(satellite_name)
+            }
+        },
         Err(e) => {
             eprintln!("⚠ Error SatNOGS: {}", e);
             println!("⚠ Usando base de datos local");
@@ -203,6 +205,8 @@ pub fn get_satellite_info(satellite_name: &str) -> Option<SatelliteFrequencies>
 ///
 /// # Argumentos
 /// * `satellite_name` - Nombre del satélite o NORAD ID como string
-pub fn obtener_frecuencia_por_nombre(satellite_name: &str) -> Option<f64> {
-    get_satellite_info(satellite_name).map(|info| info.downlink_hz)
+pub async fn obtener_frecuencia_por_nombre(satellite_name: &str) -> Option<f64> {
+    get_satellite_info(satellite_name)
+        .await
+        .map(|info| info.downlink_hz)
 }
@@ -1,7 +1,13 @@
 use serde::{Deserialize, Serialize};
 pub use sgp4::Elements;
 use std::time::Duration;
+pub mod doppler;
+pub mod ephemeris;
 pub mod frequencies;
+pub mod observacion;
+pub mod rig;
+pub mod sp3;
+pub mod timescale;
 pub mod tle_loader;
 pub mod validaciones;
 use chrono::{DateTime, Utc};
@@ -54,6 +60,11 @@ pub struct Observation {
     pub elevation: Degrees,
     /// Range rate, in meters per second.
     pub range_rate: f64,
+    /// Topocentric East/North/Up offsets from the observer, in meters.
+    pub enu: [f64; 3],
+    /// Doppler shift of the downlink carrier, in Hz, when a downlink frequency
+    /// has been configured on the tracker (see [`Tracker::set_downlink_freq`]).
+    pub doppler_hz: Option<f64>,
 }
 
 #[derive(Debug)]
@@ -62,11 +73,129 @@ pub enum TrackerError {
     OrbitPredictionError(orbit::OrbitPredictionError),
 }
 
+/// The standard Dilution of Precision figures for a ground-station geometry.
+#[derive(Debug, Clone, Copy)]
+pub struct Dop {
+    /// Geometric DOP (position + clock).
+    pub gdop: f64,
+    /// Position DOP.
+    pub pdop: f64,
+    /// Horizontal DOP.
+    pub hdop: f64,
+    /// Vertical DOP.
+    pub vdop: f64,
+    /// Time DOP.
+    pub tdop: f64,
+}
+
+#[derive(Debug)]
+pub enum DopError {
+    /// At least four satellites are required to solve for the four unknowns.
+    NotEnoughSatellites(usize),
+    /// The geometry matrix `HᵀH` is (near-)singular and cannot be inverted.
+    SingularGeometry,
+}
+
+/// Compute the geometric Dilution of Precision figures for a set of
+/// simultaneously visible satellites.
+///
+/// Each observation contributes one row `[-cos(el)·sin(az), -cos(el)·cos(az),
+/// -sin(el), 1]` to the geometry matrix `H` (east, north, up, clock). With
+/// `Q = (HᵀH)⁻¹` the figures are `GDOP = sqrt(trace Q)`, `PDOP = sqrt(Q11 +
+/// Q22 + Q33)`, `HDOP = sqrt(Q11 + Q22)`, `VDOP = sqrt(Q33)` and
+/// `TDOP = sqrt(Q44)`.
+///
+/// Returns [`DopError::NotEnoughSatellites`] with fewer than four satellites and
+/// [`DopError::SingularGeometry`] when `HᵀH` is not invertible.
+pub fn dilution_of_precision(observations: &[Observation]) -> Result<Dop, DopError> {
+    if observations.len() < 4 {
+        return Err(DopError::NotEnoughSatellites(observations.len()));
+    }
+
+    // Accumulate HᵀH directly (4x4), avoiding an explicit tall H.
+    let mut hth = [[0.0_f64; 4]; 4];
+    for obs in observations {
+        let el = obs.elevation * DEG_TO_RAD;
+        let az = obs.azimuth * DEG_TO_RAD;
+        let row = [
+            -el.cos() * az.sin(),
+            -el.cos() * az.cos(),
+            -el.sin(),
+            1.0,
+        ];
+        for i in 0..4 {
+            for j in 0..4 {
+                hth[i][j] += row[i] * row[j];
+            }
+        }
+    }
+
+    let q = invert_4x4(&hth).ok_or(DopError::SingularGeometry)?;
+
+    Ok(Dop {
+        gdop: (q[0][0] + q[1][1] + q[2][2] + q[3][3]).sqrt(),
+        pdop: (q[0][0] + q[1][1] + q[2][2]).sqrt(),
+        hdop: (q[0][0] + q[1][1]).sqrt(),
+        vdop: q[2][2].sqrt(),
+        tdop: q[3][3].sqrt(),
+    })
+}
+
+/// Invert a 4x4 matrix via Gauss-Jordan elimination with partial pivoting.
+///
+/// Returns `None` when the matrix is (near-)singular.
+fn invert_4x4(m: &[[f64; 4]; 4]) -> Option<[[f64; 4]; 4]> {
+    // Augment with the identity and reduce the left half to the identity.
+    let mut a = [[0.0_f64; 8]; 4];
+    for i in 0..4 {
+        a[i][..4].copy_from_slice(&m[i]);
+        a[i][4 + i] = 1.0;
+    }
+
+    for col in 0..4 {
+        // Partial pivot: move the largest-magnitude row into place.
+        let mut pivot = col;
+        for row in (col + 1)..4 {
+            if a[row][col].abs() > a[pivot][col].abs() {
+                pivot = row;
+            }
+        }
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+
+        let diag = a[col][col];
+        for j in 0..8 {
+            a[col][j] /= diag;
+        }
+        for row in 0..4 {
+            if row == col {
+                continue;
+            }
+            let factor = a[row][col];
+            for j in 0..8 {
+                a[row][j] -= factor * a[col][j];
+            }
+        }
+    }
+
+    let mut inv = [[0.0_f64; 4]; 4];
+    for i in 0..4 {
+        inv[i].copy_from_slice(&a[i][4..8]);
+    }
+    Some(inv)
+}
+
 /// The tracker is used to predict the position of a satellite, given its orbital elements, relative to the ground station.
 pub struct Tracker {
     observer: PredictObserver,
     elements: sgp4::Elements,
     constants: sgp4::Constants,
+    sink: Option<Box<dyn observacion::ObservationSink>>,
+    /// Downlink carrier frequency (Hz) used to compute the Doppler shift of each
+    /// observation, when set.
+    downlink_freq_hz: Option<f64>,
 }
 
 impl Tracker {
@@ -91,9 +220,23 @@ impl Tracker {
             observer,
             elements,
             constants,
+            sink: None,
+            downlink_freq_hz: None,
         })
     }
 
+    /// Configure a telemetry sink that every [`Tracker::track_and_record`] call
+    /// will publish its observation to.
+    pub fn set_sink(&mut self, sink: Box<dyn observacion::ObservationSink>) {
+        self.sink = Some(sink);
+    }
+
+    /// Configure the downlink carrier frequency (Hz) so each [`Observation`]
+    /// carries its Doppler shift.
+    pub fn set_downlink_freq(&mut self, freq_hz: f64) {
+        self.downlink_freq_hz = Some(freq_hz);
+    }
+
     /// Predict the observation of the satellite at a given time.
     ///
     /// # Arguments
@@ -107,10 +250,89 @@ impl Tracker {
 
         let observation = observer::predict_observe_orbit(&self.observer, &orbit);
 
+        // Az/el and range rate come straight from predict_rs; the ENU offsets are
+        // projected onto the WGS84 basis by `doppler::look_angles` rather than
+        // re-derived here from the look angles (which would double the spherical
+        // approximation). Fall back to the predict_rs angles if the look-angle
+        // projection is unavailable at this epoch.
+        let enu = doppler::look_angles(&self.observer, &self.elements, &self.constants, at)
+            .map(|la| la.enu)
+            .unwrap_or_else(|| {
+                let r = observation.range * 1000.0; // km to m
+                let (az, el) = (observation.azimuth, observation.elevation);
+                [r * el.cos() * az.sin(), r * el.cos() * az.cos(), r * el.sin()]
+            });
+
+        let range_rate = observation.range_rate * 1000.0; // Convert km/s to m/s
         Ok(Observation {
             azimuth: observation.azimuth * RAD_TO_DEG,
             elevation: observation.elevation * RAD_TO_DEG,
-            range_rate: observation.range_rate * 1000.0, // Convert km/s to m/s
+            range_rate,
+            enu,
+            doppler_hz: self.doppler_at(range_rate),
+        })
+    }
+
+    /// Doppler shift (Hz) of the configured downlink carrier at a given range
+    /// rate, or `None` when no downlink frequency has been set.
+    fn doppler_at(&self, range_rate: f64) -> Option<f64> {
+        self.downlink_freq_hz
+            .map(|f| -f * (range_rate / SPEED_OF_LIGHT))
+    }
+
+    /// Predict the observation at `at` and push it to the configured telemetry
+    /// sink (if any) before returning it.
+    pub fn track_and_record(&mut self, at: DateTime<Utc>) -> Result<Observation, TrackerError> {
+        let observation = self.track(at)?;
+        if let Some(sink) = self.sink.as_mut() {
+            let band_hz = self.downlink_freq_hz.unwrap_or(0.0);
+            let doppler_hz = observation.doppler_hz.unwrap_or(0.0);
+            let [e, n, u] = observation.enu;
+            let record = observacion::Observacion {
+                tiempo: at,
+                sat: self
+                    .elements
+                    .object_name
+                    .clone()
+                    .unwrap_or_else(|| self.elements.norad_id.to_string()),
+                norad_id: self.elements.norad_id,
+                band_hz,
+                range: (e * e + n * n + u * u).sqrt(),
+                range_rate: observation.range_rate,
+                elevation: observation.elevation,
+                azimuth: observation.azimuth,
+                doppler_hz,
+                freq_rx: band_hz + doppler_hz,
+            };
+            // A sink failure is reported through the log; it must not abort tracking.
+            if let Err(e) = sink.write(&record) {
+                eprintln!("⚠ Error al publicar telemetría: {}", e);
+            }
+        }
+        Ok(observation)
+    }
+
+    /// Predict the observation at `at` using precise SP3 ephemerides instead of
+    /// SGP4 propagation.
+    ///
+    /// SGP4 is accurate to roughly a kilometre; when a post-processed SP3 file is
+    /// available it becomes a second, centimetre-class source of satellite
+    /// position. The look angles and ENU offsets come from projecting onto the
+    /// observer's WGS84 ENU basis, and the range rate from projecting the
+    /// interpolated velocity onto the line of sight; [`Tracker::track`] uses that
+    /// basis only for its ENU offsets and takes its angles from predict_rs.
+    ///
+    /// Returns `None` when fewer than two samples lie within the ephemeris'
+    /// configured window of the requested epoch.
+    pub fn track_sp3(&self, ephem: &mut sp3::Sp3Ephemeris, at: DateTime<Utc>) -> Option<Observation> {
+        let (look, range_rate) = doppler::observar_sp3(&self.observer, ephem, at)?;
+
+        Some(Observation {
+            azimuth: look.azimuth,
+            elevation: look.elevation,
+            range_rate,
+            enu: look.enu,
+            doppler_hz: self.doppler_at(range_rate),
         })
     }
 
@@ -180,3 +402,52 @@ pub fn doppler_uplink(freq_rx_sat: f64, range_rate: f64) -> f64 {
     let doppler_shift = freq_rx_sat * (range_rate / SPEED_OF_LIGHT);
     freq_rx_sat + doppler_shift
 }
+
+/// Calcula la tasa de Doppler (Hz/s) a partir de la aceleración radial.
+///
+/// Útil para que un controlador pre-deslice (pre-slew) la radio entre
+/// actualizaciones en lugar de corregir a saltos discretos.
+///
+/// # Arguments
+/// * `freq_tx` - Frecuencia transmitida en Hz
+/// * `range_accel` - Aceleración radial en m/s² (derivada temporal del range rate)
+pub fn doppler_rate_hz_per_s(freq_tx: f64, range_accel: f64) -> f64 {
+    -freq_tx * (range_accel / SPEED_OF_LIGHT)
+}
+
+/// Transpondedor lineal con bandas de uplink y downlink separadas.
+#[derive(Debug, Clone, Copy)]
+pub struct LinearTransponder {
+    /// Centro de la banda de uplink, en Hz.
+    pub uplink_center: f64,
+    /// Centro de la banda de downlink, en Hz.
+    pub downlink_center: f64,
+    /// `true` si el transpondedor invierte el pasabanda (SSB cambia de banda lateral).
+    pub inverting: bool,
+}
+
+impl LinearTransponder {
+    /// Devuelve el par de sintonía `(uplink, downlink)` corregido por Doppler
+    /// para trabajar en un slot de downlink dado.
+    ///
+    /// Mapea el offset respecto del centro del downlink al uplink correspondiente
+    /// (restándolo si el transpondedor es inversor) y aplica a cada tramo su
+    /// corrección Doppler: el uplink se pre-compensa para que el satélite reciba
+    /// su frecuencia nominal y el downlink se corrige para la estación terrena.
+    ///
+    /// # Arguments
+    /// * `downlink_slot` - Frecuencia de downlink deseada en Hz
+    /// * `range_rate` - Velocidad radial en m/s (positivo = alejándose)
+    pub fn crossband(&self, downlink_slot: f64, range_rate: f64) -> (f64, f64) {
+        let offset = downlink_slot - self.downlink_center;
+        let uplink_nominal = if self.inverting {
+            self.uplink_center - offset
+        } else {
+            self.uplink_center + offset
+        };
+
+        let uplink = doppler_uplink(uplink_nominal, range_rate);
+        let downlink = doppler_downlink(downlink_slot, range_rate);
+        (uplink, downlink)
+    }
+}
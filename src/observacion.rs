@@ -0,0 +1,168 @@
+//! Registro de observación y sumideros de series temporales
+//!
+//! Centraliza la salida de tracking y validación, hoy dispersa entre `println!`
+//! y rutas de CSV hardcodeadas. Un [`Observacion`] describe una muestra completa
+//! y un [`ObservationSink`] la persiste en CSV, JSON-lines o line-protocol de
+//! InfluxDB, habilitando tanto dashboards en vivo como análisis retrospectivo.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// Una muestra de tracking lista para persistir.
+#[derive(Debug, Clone, Serialize)]
+pub struct Observacion {
+    /// Instante de la muestra.
+    pub tiempo: DateTime<Utc>,
+    /// Nombre del satélite (tag).
+    pub sat: String,
+    /// Identificador NORAD del satélite (tag).
+    pub norad_id: u64,
+    /// Banda/frecuencia nominal en Hz (tag).
+    pub band_hz: f64,
+    /// Slant range en metros.
+    pub range: f64,
+    /// Range rate en metros por segundo.
+    pub range_rate: f64,
+    /// Elevación en grados.
+    pub elevation: f64,
+    /// Azimut en grados.
+    pub azimuth: f64,
+    /// Corrimiento Doppler en Hz.
+    pub doppler_hz: f64,
+    /// Frecuencia de recepción corregida en Hz.
+    pub freq_rx: f64,
+}
+
+/// Destino de una serie temporal de [`Observacion`].
+pub trait ObservationSink {
+    /// Escribe una observación en el sumidero.
+    fn write(&mut self, obs: &Observacion) -> io::Result<()>;
+
+    /// Vacía cualquier buffer pendiente. Por defecto no hace nada.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sumidero CSV con cabecera.
+pub struct CsvSink<W: Write> {
+    writer: W,
+    header_written: bool,
+}
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            header_written: false,
+        }
+    }
+}
+
+impl<W: Write> ObservationSink for CsvSink<W> {
+    fn write(&mut self, obs: &Observacion) -> io::Result<()> {
+        if !self.header_written {
+            writeln!(
+                self.writer,
+                "timestamp,sat,norad_id,band_hz,range_m,range_rate_m_s,elevation_deg,azimuth_deg,doppler_hz,freq_rx_hz"
+            )?;
+            self.header_written = true;
+        }
+        writeln!(
+            self.writer,
+            "{},{},{},{:.0},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3}",
+            obs.tiempo.to_rfc3339(),
+            obs.sat,
+            obs.norad_id,
+            obs.band_hz,
+            obs.range,
+            obs.range_rate,
+            obs.elevation,
+            obs.azimuth,
+            obs.doppler_hz,
+            obs.freq_rx
+        )
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Sumidero JSON-lines: un objeto JSON serializado por línea.
+pub struct JsonLinesSink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: Write> ObservationSink for JsonLinesSink<W> {
+    fn write(&mut self, obs: &Observacion) -> io::Result<()> {
+        let line = serde_json::to_string(obs)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        writeln!(self.writer, "{}", line)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Sumidero en line-protocol de InfluxDB.
+///
+/// Emite una línea por muestra: `<measurement>,sat=<name>,norad=<id>,band=<freq>
+/// <fields> <timestamp_ns>`.
+pub struct InfluxLinesSink<W: Write> {
+    writer: W,
+    measurement: String,
+}
+
+impl<W: Write> InfluxLinesSink<W> {
+    pub fn new(writer: W, measurement: impl Into<String>) -> Self {
+        Self {
+            writer,
+            measurement: measurement.into(),
+        }
+    }
+}
+
+impl<W: Write> ObservationSink for InfluxLinesSink<W> {
+    fn write(&mut self, obs: &Observacion) -> io::Result<()> {
+        let timestamp_ns = obs
+            .tiempo
+            .timestamp_nanos_opt()
+            .unwrap_or_else(|| obs.tiempo.timestamp() * 1_000_000_000);
+        writeln!(
+            self.writer,
+            "{},sat={},norad={},band={:.0} range={},range_rate={},elevation={},azimuth={},doppler_hz={},freq_rx={} {}",
+            self.measurement,
+            escape_tag(&obs.sat),
+            obs.norad_id,
+            obs.band_hz,
+            obs.range,
+            obs.range_rate,
+            obs.elevation,
+            obs.azimuth,
+            obs.doppler_hz,
+            obs.freq_rx,
+            timestamp_ns
+        )
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Escapa los caracteres de un valor de tag según line-protocol.
+pub(crate) fn escape_tag(value: &str) -> String {
+    value
+        .replace(' ', "\\ ")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+}
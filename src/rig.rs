@@ -0,0 +1,98 @@
+//! Control de radio y rotor en tiempo real durante un pase
+//!
+//! Durante un pase, la frecuencia corregida por Doppler debe enviarse a un
+//! radio y los ángulos de azimut/elevación a un controlador de rotor. Este
+//! módulo define el trait [`RigController`] y una implementación concreta sobre
+//! los daemons de Hamlib (`rigctld` para el radio y `rotctld` para el rotor),
+//! además de un backend simulado para pruebas en seco.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+/// Backend capaz de sintonizar un radio y apuntar un rotor.
+pub trait RigController {
+    /// Sintoniza el radio en `hz`.
+    fn set_freq(&mut self, hz: f64) -> io::Result<()>;
+    /// Apunta el rotor a `(azimut, elevación)` en grados.
+    fn set_position(&mut self, az_deg: f64, el_deg: f64) -> io::Result<()>;
+}
+
+/// Cliente TCP de Hamlib: `rigctld` para el radio y `rotctld` para el rotor.
+///
+/// Cualquiera de los dos extremos es opcional, de modo que una estación que
+/// sólo tenga radio (o sólo rotor) pueda usar el mismo controlador.
+pub struct HamlibRig {
+    rig: Option<TcpStream>,
+    rotor: Option<TcpStream>,
+}
+
+impl HamlibRig {
+    /// Conecta a los daemons indicados (p. ej. `"127.0.0.1:4532"` para rigctld y
+    /// `"127.0.0.1:4533"` para rotctld).
+    pub fn connect(rig_addr: Option<&str>, rotor_addr: Option<&str>) -> io::Result<Self> {
+        let rig = match rig_addr {
+            Some(addr) => Some(TcpStream::connect(addr)?),
+            None => None,
+        };
+        let rotor = match rotor_addr {
+            Some(addr) => Some(TcpStream::connect(addr)?),
+            None => None,
+        };
+        Ok(Self { rig, rotor })
+    }
+}
+
+/// Envía un comando a un daemon Hamlib y verifica la respuesta `RPRT`.
+fn enviar_comando(stream: &mut TcpStream, comando: &str) -> io::Result<()> {
+    stream.write_all(comando.as_bytes())?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut respuesta = String::new();
+    reader.read_line(&mut respuesta)?;
+
+    // rigctld/rotctld responden con "RPRT <code>"; 0 indica éxito.
+    if let Some(code) = respuesta.trim().strip_prefix("RPRT ") {
+        if code.trim() != "0" {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("Hamlib devolvió error: {}", respuesta.trim()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl RigController for HamlibRig {
+    fn set_freq(&mut self, hz: f64) -> io::Result<()> {
+        if let Some(rig) = self.rig.as_mut() {
+            enviar_comando(rig, &format!("F {}\n", hz as u64))?;
+        }
+        Ok(())
+    }
+
+    fn set_position(&mut self, az_deg: f64, el_deg: f64) -> io::Result<()> {
+        if let Some(rotor) = self.rotor.as_mut() {
+            enviar_comando(rotor, &format!("P {:.2} {:.2}\n", az_deg, el_deg))?;
+        }
+        Ok(())
+    }
+}
+
+/// Backend simulado que imprime los comandos en lugar de enviarlos por red.
+///
+/// Pensado para pruebas en seco (dry-run) del bucle de tracking.
+#[derive(Debug, Default)]
+pub struct SimulatedRig;
+
+impl RigController for SimulatedRig {
+    fn set_freq(&mut self, hz: f64) -> io::Result<()> {
+        println!("[sim] set_freq {:.0} Hz", hz);
+        Ok(())
+    }
+
+    fn set_position(&mut self, az_deg: f64, el_deg: f64) -> io::Result<()> {
+        println!("[sim] set_position az={:.2}° el={:.2}°", az_deg, el_deg);
+        Ok(())
+    }
+}
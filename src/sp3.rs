@@ -0,0 +1,242 @@
+//! Efemérides precisas a partir de archivos SP3 del IGS
+//!
+//! A diferencia de SGP4 (que propaga desde un TLE con precisión del orden del
+//! kilómetro), los archivos SP3 contienen posiciones ECEF tabuladas con
+//! precisión centimétrica, pensadas para post-proceso. Este módulo parsea el
+//! formato SP3 y ofrece un interpolador basado en el algoritmo de Neville sobre
+//! una ventana deslizante para evaluar la posición en épocas arbitrarias entre
+//! las muestras tabuladas.
+
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::BTreeMap;
+
+/// Época de una muestra, en nanosegundos Unix (clave ordenable del mapa).
+///
+/// Se usan nanosegundos en vez de segundos enteros para no descartar la parte
+/// fraccionaria de la época SP3, que importa para trabajo de precisión
+/// centimétrica.
+pub type Epoch = i64;
+
+/// Kilómetros a metros.
+const KM_TO_M: f64 = 1000.0;
+
+#[derive(Debug)]
+pub enum Sp3Error {
+    /// El archivo no respeta el formato SP3 esperado.
+    FormatoInvalido(String),
+    /// No hay suficientes muestras para interpolar en la época pedida.
+    SinMuestras,
+}
+
+impl std::fmt::Display for Sp3Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sp3Error::FormatoInvalido(msg) => write!(f, "SP3 inválido: {}", msg),
+            Sp3Error::SinMuestras => write!(f, "SP3 sin muestras utilizables para la época"),
+        }
+    }
+}
+
+impl std::error::Error for Sp3Error {}
+
+/// Resultado de una interpolación: posición ECEF y, opcionalmente, su derivada
+/// temporal (útil para range-rate).
+#[derive(Debug, Clone, Copy)]
+pub struct EphemSample {
+    /// Posición ECEF en metros.
+    pub position: [f64; 3],
+    /// Velocidad ECEF en metros por segundo (derivada de la interpolación).
+    pub velocity: [f64; 3],
+}
+
+/// Efemérides SP3 de un único vehículo espacial (SV).
+///
+/// Mantiene las posiciones tabuladas en un `BTreeMap` ordenado por época y
+/// cachea la ventana de muestras seleccionada para no reconstruirla mientras la
+/// época consultada siga dentro de `[x_lower, x_upper]`.
+pub struct Sp3Ephemeris {
+    /// Posiciones ECEF tabuladas (metros) indexadas por época Unix.
+    samples: BTreeMap<Epoch, [f64; 3]>,
+    /// Cantidad máxima de muestras por ventana de interpolación.
+    max_x_size: usize,
+    /// Semiancho máximo, en segundos, de las muestras respecto de la consulta.
+    max_dx_range: f64,
+    /// Ventana cacheada: épocas seleccionadas.
+    window_x: Vec<f64>,
+    /// Ventana cacheada: posiciones asociadas.
+    window_y: Vec<[f64; 3]>,
+    /// Límites temporales de validez de la ventana cacheada.
+    x_lower: f64,
+    x_upper: f64,
+}
+
+impl Sp3Ephemeris {
+    /// Crea un contenedor vacío con los parámetros de la ventana deslizante.
+    pub fn new(max_x_size: usize, max_dx_range: f64) -> Self {
+        Self {
+            samples: BTreeMap::new(),
+            max_x_size,
+            max_dx_range,
+            window_x: Vec::new(),
+            window_y: Vec::new(),
+            x_lower: f64::INFINITY,
+            x_upper: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Parsea un archivo SP3 en memoria y construye las efemérides para el SV
+    /// indicado (p. ej. `"L01"` o `"G05"`).
+    ///
+    /// Reconoce las líneas de época (`*  YYYY MM DD HH MM SS.ssss`) y los
+    /// registros de posición (`P<SV> X Y Z ...` con X/Y/Z en km).
+    pub fn from_sp3(content: &str, sv: &str, max_x_size: usize, max_dx_range: f64) -> Result<Self, Sp3Error> {
+        let mut ephem = Self::new(max_x_size, max_dx_range);
+        let mut current_epoch: Option<Epoch> = None;
+
+        for line in content.lines() {
+            if let Some(rest) = line.strip_prefix('*') {
+                current_epoch = Some(parse_epoch_line(rest)?);
+            } else if let Some(rest) = line.strip_prefix('P') {
+                let mut fields = rest.split_whitespace();
+                let name = fields
+                    .next()
+                    .ok_or_else(|| Sp3Error::FormatoInvalido("registro P sin SV".to_string()))?;
+                if name != sv {
+                    continue;
+                }
+                let epoch = current_epoch
+                    .ok_or_else(|| Sp3Error::FormatoInvalido("registro P sin época previa".to_string()))?;
+                let x = parse_coord(fields.next())?;
+                let y = parse_coord(fields.next())?;
+                let z = parse_coord(fields.next())?;
+                ephem
+                    .samples
+                    .insert(epoch, [x * KM_TO_M, y * KM_TO_M, z * KM_TO_M]);
+            }
+        }
+
+        if ephem.samples.is_empty() {
+            return Err(Sp3Error::SinMuestras);
+        }
+
+        Ok(ephem)
+    }
+
+    /// Inserta manualmente una muestra ECEF (metros) en una época dada
+    /// (nanosegundos Unix).
+    pub fn insert(&mut self, epoch: Epoch, position_m: [f64; 3]) {
+        self.samples.insert(epoch, position_m);
+        // La ventana cacheada podría quedar obsoleta.
+        self.x_lower = f64::INFINITY;
+        self.x_upper = f64::NEG_INFINITY;
+    }
+
+    /// Interpola la posición (y su derivada) en la época `at` mediante Neville.
+    pub fn interpolate(&mut self, at: DateTime<Utc>) -> Result<EphemSample, Sp3Error> {
+        let x = at.timestamp() as f64 + f64::from(at.timestamp_subsec_nanos()) * 1e-9;
+
+        if x < self.x_lower || x > self.x_upper {
+            self.rebuild_window(x)?;
+        }
+
+        Ok(neville(&self.window_x, &self.window_y, x))
+    }
+
+    /// Selecciona las muestras cercanas a `x` y actualiza la ventana cacheada.
+    fn rebuild_window(&mut self, x: f64) -> Result<(), Sp3Error> {
+        // Las claves están en nanosegundos; se interpola en segundos para
+        // mantener el polinomio bien condicionado.
+        let mut selected: Vec<(f64, [f64; 3])> = self
+            .samples
+            .iter()
+            .map(|(&t, &p)| (t as f64 * 1e-9, p))
+            .filter(|(t, _)| (t - x).abs() <= self.max_dx_range)
+            .collect();
+
+        if selected.len() < 2 {
+            return Err(Sp3Error::SinMuestras);
+        }
+
+        // Conservar las `max_x_size` muestras más cercanas a la consulta.
+        selected.sort_by(|a, b| {
+            (a.0 - x)
+                .abs()
+                .partial_cmp(&(b.0 - x).abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        selected.truncate(self.max_x_size);
+        selected.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        self.x_lower = selected.first().map(|(t, _)| *t).unwrap_or(x);
+        self.x_upper = selected.last().map(|(t, _)| *t).unwrap_or(x);
+        self.window_x = selected.iter().map(|(t, _)| *t).collect();
+        self.window_y = selected.iter().map(|(_, p)| *p).collect();
+
+        Ok(())
+    }
+}
+
+/// Parsea la parte de una línea de época SP3 (`  YYYY MM DD HH MM SS.ssss`).
+fn parse_epoch_line(rest: &str) -> Result<Epoch, Sp3Error> {
+    let mut f = rest.split_whitespace();
+    let mut next_int = || -> Result<i32, Sp3Error> {
+        f.next()
+            .and_then(|s| s.parse::<i32>().ok())
+            .ok_or_else(|| Sp3Error::FormatoInvalido("línea de época incompleta".to_string()))
+    };
+    let year = next_int()?;
+    let month = next_int()? as u32;
+    let day = next_int()? as u32;
+    let hour = next_int()? as u32;
+    let min = next_int()? as u32;
+    let sec_field = f
+        .next()
+        .and_then(|s| s.parse::<f64>().ok())
+        .ok_or_else(|| Sp3Error::FormatoInvalido("segundos de época inválidos".to_string()))?;
+
+    // Conservar la parte fraccionaria de los segundos como nanosegundos.
+    let whole_sec = sec_field.trunc() as u32;
+    let nanos = ((sec_field - sec_field.trunc()) * 1e9).round() as i64;
+
+    Utc.with_ymd_and_hms(year, month, day, hour, min, whole_sec)
+        .single()
+        .map(|dt| dt.timestamp() * 1_000_000_000 + nanos)
+        .ok_or_else(|| Sp3Error::FormatoInvalido("fecha de época fuera de rango".to_string()))
+}
+
+fn parse_coord(field: Option<&str>) -> Result<f64, Sp3Error> {
+    field
+        .and_then(|s| s.parse::<f64>().ok())
+        .ok_or_else(|| Sp3Error::FormatoInvalido("coordenada no numérica".to_string()))
+}
+
+/// Algoritmo de Neville sobre los puntos `(xs[i], ys[i])`, evaluado en `x`.
+///
+/// Calcula en paralelo la posición interpolada y su derivada temporal,
+/// aplicando la recurrencia componente a componente.
+fn neville(xs: &[f64], ys: &[[f64; 3]], x: f64) -> EphemSample {
+    let n = xs.len();
+    let mut position = [0.0; 3];
+    let mut velocity = [0.0; 3];
+
+    for axis in 0..3 {
+        // P[i][j] y su derivada dP[i][j] se almacenan por diagonal.
+        let mut p: Vec<f64> = ys.iter().map(|y| y[axis]).collect();
+        let mut dp = vec![0.0_f64; n];
+
+        for j in 1..n {
+            for i in 0..(n - j) {
+                let xi = xs[i];
+                let xj = xs[i + j];
+                let denom = xi - xj;
+                dp[i] = (p[i] - p[i + 1] + (x - xj) * dp[i] + (xi - x) * dp[i + 1]) / denom;
+                p[i] = ((x - xj) * p[i] + (xi - x) * p[i + 1]) / denom;
+            }
+        }
+
+        position[axis] = p[0];
+        velocity[axis] = dp[0];
+    }
+
+    EphemSample { position, velocity }
+}
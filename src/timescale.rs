@@ -0,0 +1,37 @@
+//! Abstracción de escalas de tiempo (UTC/TAI/GPST) respaldada por hifitime
+//!
+//! Calcular rango, Doppler y GMST a partir de `timestamp() as f64` asume UTC e
+//! ignora silenciosamente los segundos intercalares y los offsets UTC↔GPS/TAI,
+//! que importan para Doppler preciso y para alinear con datos referidos a GNSS.
+//! Este módulo permite que las épocas lleven su escala explícita y que las
+//! conversiones entre UTC, TAI y GPST sean explícitas.
+
+use chrono::{DateTime, Utc};
+use hifitime::Epoch;
+
+/// Construye una época a partir de un instante UTC de chrono.
+pub fn epoch_from_utc(dt: DateTime<Utc>) -> Epoch {
+    let seconds = dt.timestamp() as f64 + f64::from(dt.timestamp_subsec_nanos()) * 1e-9;
+    Epoch::from_unix_seconds(seconds)
+}
+
+/// Construye una época a partir de segundos en escala TAI.
+pub fn epoch_from_tai_seconds(seconds: f64) -> Epoch {
+    Epoch::from_tai_seconds(seconds)
+}
+
+/// Construye una época a partir de segundos en escala GPST.
+pub fn epoch_from_gpst_seconds(seconds: f64) -> Epoch {
+    Epoch::from_gpst_seconds(seconds)
+}
+
+/// Segundos Unix en escala UTC (referencia UT1≈UTC usada por GMST).
+pub fn utc_seconds(epoch: Epoch) -> f64 {
+    epoch.to_unix_seconds()
+}
+
+/// Segundos en escala TAI (continua, sin discontinuidades por segundos
+/// intercalares).
+pub fn tai_seconds(epoch: Epoch) -> f64 {
+    epoch.to_tai_seconds()
+}
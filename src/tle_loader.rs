@@ -1,7 +1,11 @@
+use crate::{Observer, Tracker};
+use chrono::{DateTime, Duration, Utc};
+use sgp4::Elements;
+use std::collections::HashMap;
 use std::fs;
 use std::io::{self, BufRead};
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::time::Duration as StdDuration;
 
 #[derive(Debug)]
 pub struct TleData {
@@ -10,6 +14,18 @@ pub struct TleData {
     pub line2: String,
 }
 
+impl TleData {
+    /// Parsea este TLE a `Elements` de SGP4.
+    pub fn to_elements(&self) -> io::Result<Elements> {
+        Elements::from_tle(
+            Some(self.name.clone()),
+            self.line1.as_bytes(),
+            self.line2.as_bytes(),
+        )
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("TLE inválido: {}", e)))
+    }
+}
+
 pub fn cargar_tle_desde_archivo<P: AsRef<Path>>(path: P) -> io::Result<TleData> {
     let file = fs::File::open(path)?;
     let reader = io::BufReader::new(file);
@@ -39,22 +55,25 @@ pub fn cargar_tle_desde_archivo<P: AsRef<Path>>(path: P) -> io::Result<TleData>
 ///
 /// # Argumentos
 /// * `norad_id` - El ID NORAD del satélite (ej: 25544 para ISS)
-pub fn descargar_tle_celestrak(norad_id: u32) -> io::Result<TleData> {
+pub async fn descargar_tle_celestrak(norad_id: u32) -> io::Result<TleData> {
     let url = format!(
         "https://celestrak.org/NORAD/elements/gp.php?CATNR={}&FORMAT=TLE",
         norad_id
     );
 
-    let output = Command::new("curl").args(["-s", &url]).output()?;
-
-    if !output.status.success() {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "Error al descargar TLE desde CelesTrak",
-        ));
-    }
+    let content = reqwest::get(&url)
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("Error al descargar TLE desde CelesTrak: {}", e),
+            )
+        })?
+        .text()
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Respuesta inválida: {}", e)))?;
 
-    let content = String::from_utf8_lossy(&output.stdout);
     let lines: Vec<&str> = content.lines().collect();
 
     if lines.len() < 3 {
@@ -71,6 +90,290 @@ pub fn descargar_tle_celestrak(norad_id: u32) -> io::Result<TleData> {
     })
 }
 
+/// Catálogo persistente de TLEs de una estación terrena.
+///
+/// Mantiene los elementos orbitales de muchos satélites indexados por NORAD id,
+/// los guarda en disco y sólo vuelve a descargarlos desde CelesTrak cuando la
+/// época del conjunto almacenado supera una antigüedad máxima configurable. Si
+/// la red no está disponible, recae en el último conjunto cacheado.
+pub struct TleRepo {
+    /// Directorio donde se persisten los archivos `<norad>.tle`.
+    cache_dir: PathBuf,
+    /// Antigüedad máxima de la época antes de forzar una re-descarga.
+    max_age: Duration,
+    /// Elementos cargados en memoria, por NORAD id.
+    entries: HashMap<u32, Elements>,
+}
+
+impl TleRepo {
+    /// Crea un repositorio que persiste en `cache_dir` y considera obsoleto todo
+    /// conjunto cuya época supere `max_age`.
+    pub fn new<P: AsRef<Path>>(cache_dir: P, max_age: Duration) -> io::Result<Self> {
+        let cache_dir = cache_dir.as_ref().to_path_buf();
+        fs::create_dir_all(&cache_dir)?;
+        Ok(Self {
+            cache_dir,
+            max_age,
+            entries: HashMap::new(),
+        })
+    }
+
+    /// Carga (o refresca) una lista de vigilancia de NORAD ids en un solo lote.
+    pub async fn load_watchlist(&mut self, norad_ids: &[u32]) -> io::Result<()> {
+        for &norad_id in norad_ids {
+            self.load(norad_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Garantiza que el TLE de `norad_id` esté disponible y actualizado.
+    ///
+    /// Usa la copia en disco mientras su época sea más reciente que `max_age`;
+    /// en caso contrario intenta re-descargar, cayendo en la copia cacheada si
+    /// la red falla.
+    pub async fn load(&mut self, norad_id: u32) -> io::Result<&Elements> {
+        let cached = self.read_from_disk(norad_id);
+
+        let fresh = cached
+            .as_ref()
+            .map(|tle| self.is_fresh(tle))
+            .unwrap_or(false);
+
+        let tle = if fresh {
+            cached.unwrap()
+        } else {
+            match descargar_tle_celestrak(norad_id).await {
+                Ok(tle) => {
+                    self.write_to_disk(norad_id, &tle)?;
+                    tle
+                }
+                Err(e) => match cached {
+                    Some(tle) => {
+                        eprintln!("⚠ Sin red ({}), usando TLE cacheado de {}", e, norad_id);
+                        tle
+                    }
+                    None => return Err(e),
+                },
+            }
+        };
+
+        let elements = tle.to_elements()?;
+        // Sobrescribir siempre: si acabamos de re-descargar, la copia en memoria
+        // debe reflejar la época nueva en vez de conservar la anterior.
+        self.entries.insert(norad_id, elements);
+        Ok(self.entries.get(&norad_id).expect("recién insertado"))
+    }
+
+    /// Devuelve los elementos ya cargados de un satélite, si existen.
+    pub fn get(&self, norad_id: u32) -> Option<&Elements> {
+        self.entries.get(&norad_id)
+    }
+
+    /// Devuelve una copia de los elementos, lista para pasar a `Tracker::new`.
+    pub fn elements(&self, norad_id: u32) -> Option<Elements> {
+        self.entries.get(&norad_id).cloned()
+    }
+
+    /// Indica si la época de un TLE es más reciente que `max_age`.
+    fn is_fresh(&self, tle: &TleData) -> bool {
+        match tle.to_elements() {
+            Ok(elements) => Utc::now() - elements.datetime.and_utc() < self.max_age,
+            Err(_) => false,
+        }
+    }
+
+    fn cache_path(&self, norad_id: u32) -> PathBuf {
+        self.cache_dir.join(format!("{}.tle", norad_id))
+    }
+
+    fn read_from_disk(&self, norad_id: u32) -> Option<TleData> {
+        cargar_tle_desde_archivo(self.cache_path(norad_id)).ok()
+    }
+
+    fn write_to_disk(&self, norad_id: u32, tle: &TleData) -> io::Result<()> {
+        let content = format!("{}\n{}\n{}\n", tle.name, tle.line1, tle.line2);
+        fs::write(self.cache_path(norad_id), content)
+    }
+}
+
+/// Una entrada del catálogo: un satélite, sus elementos orbitales y, si se
+/// conoce, su frecuencia de downlink.
+#[derive(Debug, Clone)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub norad_id: u32,
+    pub elements: Elements,
+    /// Frecuencia de downlink en Hz, si se conoce.
+    pub freq_hz: Option<f64>,
+}
+
+/// Catálogo multi-satélite indexado por nombre y por NORAD id.
+///
+/// A diferencia de [`TleRepo`] —que cachea en disco y refresca un puñado de
+/// satélites vigilados desde CelesTrak—, el catálogo es una vista en memoria de
+/// un archivo TLE completo, consultable por nombre o NORAD id, que sirve de base
+/// al planificador de pases.
+#[derive(Default)]
+pub struct SatelliteCatalog {
+    by_norad: HashMap<u32, CatalogEntry>,
+    by_name: HashMap<String, u32>,
+}
+
+impl SatelliteCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ingiere un archivo TLE con uno o más satélites (bloques de 3 líneas).
+    ///
+    /// Devuelve la cantidad de satélites agregados.
+    pub fn ingest_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<usize> {
+        let file = fs::File::open(path)?;
+        let reader = io::BufReader::new(file);
+        let lines: Vec<String> = reader
+            .lines()
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|l| !l.trim().is_empty())
+            .collect();
+
+        let mut added = 0;
+        for chunk in lines.chunks(3) {
+            if chunk.len() < 3 {
+                break;
+            }
+            let tle = TleData {
+                name: chunk[0].trim().to_string(),
+                line1: chunk[1].trim().to_string(),
+                line2: chunk[2].trim().to_string(),
+            };
+            self.ingest(&tle)?;
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    /// Ingiere un único TLE en el catálogo.
+    pub fn ingest(&mut self, tle: &TleData) -> io::Result<()> {
+        let elements = tle.to_elements()?;
+        let norad_id = elements.norad_id as u32;
+        let entry = CatalogEntry {
+            name: tle.name.clone(),
+            norad_id,
+            elements,
+            freq_hz: None,
+        };
+        self.by_name.insert(tle.name.to_uppercase(), norad_id);
+        self.by_norad.insert(norad_id, entry);
+        Ok(())
+    }
+
+    /// Asigna la frecuencia de downlink (Hz) de un satélite ya ingerido.
+    ///
+    /// Permite poblar las frecuencias una sola vez (p. ej. desde SatNOGS) y
+    /// reutilizarlas en la planificación sin volver a salir a la red por cada
+    /// pase.
+    pub fn set_frequency(&mut self, norad_id: u32, freq_hz: f64) {
+        if let Some(entry) = self.by_norad.get_mut(&norad_id) {
+            entry.freq_hz = Some(freq_hz);
+        }
+    }
+
+    pub fn get_by_norad(&self, norad_id: u32) -> Option<&CatalogEntry> {
+        self.by_norad.get(&norad_id)
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&CatalogEntry> {
+        self.by_name
+            .get(&name.to_uppercase())
+            .and_then(|id| self.by_norad.get(id))
+    }
+
+    /// Itera sobre todas las entradas del catálogo.
+    pub fn entries(&self) -> impl Iterator<Item = &CatalogEntry> {
+        self.by_norad.values()
+    }
+}
+
+/// Un pase planificado de un satélite sobre la estación.
+#[derive(Debug, Clone)]
+pub struct PlannedPass {
+    pub norad_id: u32,
+    pub name: String,
+    pub aos: DateTime<Utc>,
+    pub los: DateTime<Utc>,
+    /// Frecuencia de downlink en Hz, si se conoce.
+    pub freq_hz: Option<f64>,
+    /// `true` si este pase se solapa con algún pase anterior en la agenda.
+    pub conflict: bool,
+}
+
+/// Planifica los pases de todos los satélites del catálogo dentro de una
+/// ventana, ordenados por AOS y marcando los solapamientos.
+///
+/// Cada pase lleva la frecuencia de downlink ya cargada en su [`CatalogEntry`]
+/// (ver [`SatelliteCatalog::set_frequency`]), de modo que la planificación no
+/// sale a la red por satélite y la estación pueda planificar una noche de
+/// tracking automatizado en una sola pasada.
+pub fn planificar_pases(
+    repo: &SatelliteCatalog,
+    observer: &Observer,
+    desde: DateTime<Utc>,
+    ventana: StdDuration,
+) -> Vec<PlannedPass> {
+    let mut planned = Vec::new();
+
+    for entry in repo.entries() {
+        let tracker = match Tracker::new(observer, entry.elements.clone()) {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        let Some(passes) = tracker.next_passes(desde, ventana) else {
+            continue;
+        };
+
+        let freq_hz = entry.freq_hz;
+
+        for pass in passes.passes {
+            let (Some(aos), Some(los)) = (pass.aos, pass.los) else {
+                continue;
+            };
+            let (Some(aos), Some(los)) = (
+                DateTime::from_timestamp(aos.time as i64, 0),
+                DateTime::from_timestamp(los.time as i64, 0),
+            ) else {
+                continue;
+            };
+            planned.push(PlannedPass {
+                norad_id: entry.norad_id,
+                name: entry.name.clone(),
+                aos,
+                los,
+                freq_hz,
+                conflict: false,
+            });
+        }
+    }
+
+    // Ordenar por AOS y marcar solapamientos con cualquier pase previo (el LOS
+    // más tardío visto hasta ahora).
+    planned.sort_by_key(|p| p.aos);
+    let mut last_los: Option<DateTime<Utc>> = None;
+    for pass in &mut planned {
+        if let Some(prev_los) = last_los {
+            if pass.aos < prev_los {
+                pass.conflict = true;
+            }
+        }
+        last_los = Some(match last_los {
+            Some(prev) if prev > pass.los => prev,
+            _ => pass.los,
+        });
+    }
+
+    planned
+}
+
 /// Obtiene el TLE de un satélite por su nombre
 ///
 /// Soporta los siguientes satélites:
@@ -79,7 +382,7 @@ pub fn descargar_tle_celestrak(norad_id: u32) -> io::Result<TleData> {
 /// - FO-29 / JAS-2 (NORAD 24278)
 /// - FUNCUBE-1 / AO-73 (NORAD 39444)
 /// - LILACSAT-2 / CAS-3H (NORAD 40069)
-pub fn obtener_tle_por_nombre(satellite_name: &str) -> io::Result<TleData> {
+pub async fn obtener_tle_por_nombre(satellite_name: &str) -> io::Result<TleData> {
     let norad_id = match satellite_name.to_uppercase().as_str() {
         "ISS" => 25544,
         "AO-91" | "FOX-1B" | "RADFXSAT" => 43017, // AO-91 = FOX-1B = RADFXSAT
@@ -96,7 +399,7 @@ pub fn obtener_tle_por_nombre(satellite_name: &str) -> io::Result<TleData> {
 
     println!("Descargando TLE de {}...", satellite_name);
 
-    match descargar_tle_celestrak(norad_id) {
+    match descargar_tle_celestrak(norad_id).await {
         Ok(tle) => {
             println!("✓ TLE descargado");
             Ok(tle)
@@ -1,28 +1,25 @@
 use crate::frequencies;
+use crate::observacion::{Observacion, ObservationSink};
 use chrono::{DateTime, Duration, Utc};
 use predict_rs::{observer::predict_observe_orbit, orbit::predict_orbit, predict::PredictObserver};
 use sgp4::{Constants, Elements};
-use std::fs::File;
-use std::io::Write;
+use std::io::{self, Write};
 
-pub fn generar_comparacion(
+/// Velocidad de la luz en metros por segundo
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+pub async fn generar_comparacion(
     observer: &PredictObserver,
     elements: &Elements,
     constants: &Constants,
     inicio: DateTime<Utc>,
     duracion_mins: i64,
+    sink: &mut dyn ObservationSink,
 ) -> std::io::Result<()> {
     // Obtener frecuencia de ISS
-    let freq =
-        frequencies::obtener_frecuencia_por_nombre("ISS").expect("Frecuencia de ISS no encontrada");
-    let freq_mhz = freq / 1_000_000.0;
-
-    let mut file = File::create("validacion_doppler/iss/doppler_output.csv")?;
-    writeln!(
-        file,
-        "timestamp,range_m,range_rate_m_s,doppler_{:.1}MHz_Hz",
-        freq_mhz
-    )?;
+    let freq = frequencies::obtener_frecuencia_por_nombre("ISS")
+        .await
+        .expect("Frecuencia de ISS no encontrada");
 
     let mut puntos_validos = 0;
     let mut puntos_invalidos = 0;
@@ -40,23 +37,26 @@ pub fn generar_comparacion(
             let range_rate = observation.range_rate * 1000.0; // km/s a m/s
 
             // Calcular Doppler usando range_rate
-            let doppler = -freq * (range_rate / 299_792_458.0); // SPEED_OF_LIGHT
+            let doppler = -freq * (range_rate / SPEED_OF_LIGHT);
 
-            writeln!(
-                file,
-                "{},{:.0},{:.2},{:.0}",
-                cuando.to_rfc3339(),
-                rango,
+            sink.write(&Observacion {
+                tiempo: cuando,
+                sat: "ISS".to_string(),
+                norad_id: elements.norad_id,
+                band_hz: freq,
+                range: rango,
                 range_rate,
-                doppler
-            )?;
+                elevation: observation.elevation.to_degrees(),
+                azimuth: observation.azimuth.to_degrees(),
+                doppler_hz: doppler,
+                freq_rx: freq + doppler,
+            })?;
 
             puntos_validos += 1;
 
             // Mostrar progreso cada 10 puntos
             if (minuto + 1) % 10 == 0 {
                 print!(".");
-                use std::io::{self, Write};
                 io::stdout().flush().unwrap();
             }
         } else {
@@ -64,6 +64,8 @@ pub fn generar_comparacion(
         }
     }
 
+    sink.flush()?;
+
     println!(" ✓");
     println!("{} válidos, {} inválidos", puntos_validos, puntos_invalidos);
 